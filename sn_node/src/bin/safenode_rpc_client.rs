@@ -12,23 +12,71 @@ use clap::Parser;
 use eyre::{eyre, Result};
 use libp2p::{Multiaddr, PeerId};
 use safenode_proto::{
-    safe_node_client::SafeNodeClient, GossipsubPublishRequest, GossipsubSubscribeRequest,
-    GossipsubUnsubscribeRequest, NetworkInfoRequest, NodeEventsRequest, NodeInfoRequest,
-    RecordAddressesRequest, RestartRequest, SetRewardsAddressRequest, StopRequest,
+    safe_node_client::SafeNodeClient, CreditsRequest, GossipsubPublishRequest,
+    GossipsubSubscribeRequest, GossipsubUnsubscribeRequest, NetworkInfoRequest, NodeEventsRequest,
+    NodeInfoRequest, RecordAddressesRequest, RestartRequest, SetRewardsAddressRequest, StopRequest,
     TransferNotifsFilterRequest, UpdateRequest,
 };
 use sn_client::Client;
-use sn_logging::LogBuilder;
+use sn_logging::{LogBuilder, LogOutputDest};
 use sn_node::NodeEvent;
 use sn_peers_acquisition::{parse_peers_args, PeersArgs};
 use sn_protocol::storage::SpendAddress;
-use sn_transfers::{LocalWallet, MainSecretKey, Transfer};
-use std::{fs, net::SocketAddr, path::PathBuf, str::FromStr, time::Duration};
+use sn_transfers::{CashNote, DerivationIndex, LocalWallet, MainSecretKey, Transfer};
+use std::{
+    fs,
+    io::Write,
+    net::SocketAddr,
+    path::{Path, PathBuf},
+    str::FromStr,
+    time::Duration,
+};
+use tokio::io::{AsyncBufReadExt, BufReader};
 use tokio_stream::StreamExt;
-use tonic::Request;
+use tonic::{transport::Channel, Code, Request, Response, Status};
 use tracing::warn;
 use tracing_core::Level;
 
+use keystore::Keystore;
+
+mod keystore;
+
+/// An RPC client connected to a single node, reused across calls instead of
+/// reconnecting for every command.
+type RpcClient = SafeNodeClient<Channel>;
+
+/// Environment variable consulted for a secret key when neither the positional
+/// argument, `--secret-key-file`, nor `--key-alias` were given.
+const SECRET_KEY_ENV_VAR: &str = "SN_NODE_CLIENT_SECRET_KEY";
+
+/// gRPC status code the node returns when a caller's credit balance can't cover
+/// the cost of the requested RPC. Mirrors `sn_node::rpc_service::credits::INSUFFICIENT_CREDITS_CODE`
+/// (see that module's doc comment for the accounting logic and the remaining step
+/// needed to call it from the RPC handlers).
+const INSUFFICIENT_CREDITS_CODE: Code = Code::ResourceExhausted;
+
+/// Turns the generic tonic error raised when a client has run out of request
+/// credits into a short, actionable message instead of the raw gRPC status.
+///
+/// There's deliberately no client-side pre-check before dispatching a costly call:
+/// the balance lives on the node and recharges continuously, so a check-then-call
+/// here would just race the server's own enforcement without saving anything. The
+/// `credits` subcommand exists for a caller that wants to consult the balance on
+/// its own terms; routine command paths just make the call and let the node decide.
+fn map_rpc_status<T>(result: std::result::Result<Response<T>, Status>) -> Result<Response<T>> {
+    result.map_err(|status| {
+        if status.code() == INSUFFICIENT_CREDITS_CODE {
+            eyre!(
+                "Insufficient credits to perform this request: {}. Run the `credits` \
+                 subcommand to check your current balance and the RPC cost table.",
+                status.message()
+            )
+        } else {
+            eyre!("RPC call failed: {status}")
+        }
+    })
+}
+
 // this includes code generated from .proto files
 mod safenode_proto {
     tonic::include_proto!("safenode_proto");
@@ -39,11 +87,55 @@ mod safenode_proto {
 struct Opt {
     /// Address of the node's RPC service, e.g. 127.0.0.1:12001.
     addr: SocketAddr,
+    /// Override the log level for a specific target, e.g. `sn_transfers=debug`. May be
+    /// passed multiple times. Also accepts a `RUST_LOG`-style comma-separated list
+    /// (`target=level,target=level`), or a single bare level applied to every target.
+    #[clap(long = "log-level", value_name = "TARGET=LEVEL")]
+    log_level: Vec<String>,
+    /// Where to send log output: `stdout`, or a directory path to log to a file there.
+    #[clap(long = "log-output", default_value = "stdout")]
+    log_output: LogOutputDest,
     /// subcommands
     #[clap(subcommand)]
     cmd: Cmd,
 }
 
+/// Merges a `target=level` (or comma-separated list of them) spec into `targets`,
+/// overriding the level for targets already present and appending any new ones. A
+/// segment with no `=` is treated as a bare level applied to every existing target,
+/// matching the common shorthand form of `RUST_LOG=debug`.
+fn merge_log_level(targets: &mut Vec<(String, Level)>, spec: &str) -> Result<()> {
+    for part in spec.split(',') {
+        let part = part.trim();
+        if part.is_empty() {
+            continue;
+        }
+
+        match part.split_once('=') {
+            Some((target, level)) => {
+                let level = parse_log_level(level)?;
+                match targets.iter_mut().find(|(t, _)| t == target) {
+                    Some(entry) => entry.1 = level,
+                    None => targets.push((target.to_string(), level)),
+                }
+            }
+            None => {
+                let level = parse_log_level(part)?;
+                for entry in targets.iter_mut() {
+                    entry.1 = level;
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn parse_log_level(s: &str) -> Result<Level> {
+    s.parse::<Level>()
+        .map_err(|_| eyre!("Invalid log level '{s}', expected one of: trace, debug, info, warn, error"))
+}
+
 #[derive(Parser, Debug)]
 enum Cmd {
     /// Retrieve information about the node itself
@@ -52,6 +144,16 @@ enum Cmd {
     /// Retrieve information about the node's connections to the network
     #[clap(name = "netinfo")]
     Netinfo,
+    /// Fetch the RPC cost table and this client's current credit balance.
+    /// Useful for scripts that want to self-throttle before the node rejects calls for
+    /// insufficient credits.
+    #[clap(name = "credits")]
+    Credits,
+    /// Open a single RPC connection and keep it around for an interactive session:
+    /// node events stream in the background while subcommands can still be issued
+    /// from a prompt, e.g. `info`, `netinfo`, `subscribe <topic>`, `publish <topic> <msg>`.
+    #[clap(name = "interactive")]
+    Interactive,
     /// Start listening for node events.
     /// Note this blocks the app and it will print events as they are broadcasted by the node
     #[clap(name = "events")]
@@ -60,14 +162,33 @@ enum Cmd {
     /// Note this blocks the app and it will print events as they are broadcasted by the node
     #[clap(name = "transfers")]
     TransfersEvents {
-        /// The hex-encoded BLS secret key to decrypt the transfers received and convert
-        /// them into spendable CashNotes.
-        sk: String,
+        #[command(flatten)]
+        key: SecretKeyArgs,
         /// Path where to store CashNotes received.
         /// Each CashNote is written to a separate file in respective
         /// recipient public address dir in the created cash_notes dir.
         /// Each file is named after the CashNote id.
-        #[clap(name = "log-cash-notes")]
+        #[clap(long = "log-cash-notes")]
+        log_cash_notes: Option<PathBuf>,
+
+        #[command(flatten)]
+        peers: PeersArgs,
+    },
+    /// Reconstruct wallet balance by scanning the network directly, recovering CashNotes
+    /// that arrived while the `transfers` listener wasn't running. Walks sequentially
+    /// derived addresses and stops once `stop-gap` consecutive ones are found empty.
+    #[clap(name = "rescan")]
+    Rescan {
+        #[command(flatten)]
+        key: SecretKeyArgs,
+        /// Number of consecutive empty addresses to see before the sweep stops. Must be
+        /// at least 1, since a gap of 0 would mean the sweep never probes anything.
+        #[clap(long, default_value = "20", value_parser = clap::value_parser!(u64).range(1..))]
+        stop_gap: u64,
+        /// Path where to store CashNotes found during the rescan, using the same
+        /// layout as `transfers`: one `<spend address>.cash_note` file per CashNote,
+        /// written directly into this directory.
+        #[clap(long = "log-cash-notes")]
         log_cash_notes: Option<PathBuf>,
 
         #[command(flatten)]
@@ -96,9 +217,8 @@ enum Cmd {
     /// Set the address the node shall request its rewards to be sent/paid to.
     #[clap(name = "rewards")]
     RewardsAddress {
-        /// The SecretKey corresponding to the address the node shall request
-        /// its rewards to be sent/paid to.
-        sk: String,
+        #[command(flatten)]
+        key: SecretKeyArgs,
     },
     /// Restart the node after the specified delay
     #[clap(name = "restart")]
@@ -123,59 +243,230 @@ enum Cmd {
     },
 }
 
+/// Selects where a BLS secret key comes from. At most one of the three may be set;
+/// `conflicts_with_all` makes clap reject ambiguous combinations up front. If none is
+/// set, the key is read from the `SN_NODE_CLIENT_SECRET_KEY` environment variable.
+#[derive(clap::Args, Debug)]
+struct SecretKeyArgs {
+    /// The hex-encoded BLS secret key. Avoid this outside of local testing: it leaks
+    /// into shell history and is visible to other processes via `ps`/`/proc`. Prefer
+    /// `--secret-key-file` or `--key-alias`.
+    #[clap(conflicts_with_all = ["secret_key_file", "key_alias"])]
+    sk: Option<String>,
+    /// Path to a file holding the hex-encoded secret key. The file must have no
+    /// permission bits set for group/other.
+    #[clap(long, conflicts_with_all = ["sk", "key_alias"])]
+    secret_key_file: Option<PathBuf>,
+    /// Alias of a key stored in the local keystore (`~/.safe/node/keystore`), so the
+    /// raw key material never has to appear on the command line.
+    #[clap(long, conflicts_with_all = ["sk", "secret_key_file"])]
+    key_alias: Option<String>,
+}
+
+/// Resolves a BLS secret key from whichever source the caller selected, falling back
+/// to the `SN_NODE_CLIENT_SECRET_KEY` environment variable if none of the CLI options
+/// were given.
+fn resolve_secret_key(key: SecretKeyArgs) -> Result<SecretKey> {
+    if let Some(sk) = key.sk {
+        return SecretKey::from_hex(&sk)
+            .map_err(|err| eyre!("Failed to parse hex-encoded SK: {err:?}"));
+    }
+
+    if let Some(path) = key.secret_key_file {
+        let hex = read_secret_key_file(&path)?;
+        return SecretKey::from_hex(hex.trim()).map_err(|err| {
+            eyre!(
+                "Failed to parse hex-encoded SK read from {}: {err:?}",
+                path.display()
+            )
+        });
+    }
+
+    if let Some(alias) = key.key_alias {
+        return Keystore::load_default()?.get(&alias);
+    }
+
+    if let Ok(hex) = std::env::var(SECRET_KEY_ENV_VAR) {
+        return SecretKey::from_hex(hex.trim()).map_err(|err| {
+            eyre!("Failed to parse hex-encoded SK read from ${SECRET_KEY_ENV_VAR}: {err:?}")
+        });
+    }
+
+    Err(eyre!(
+        "No secret key provided. Pass it directly, or use --secret-key-file, --key-alias, \
+         or set the ${SECRET_KEY_ENV_VAR} environment variable."
+    ))
+}
+
+/// Reads a secret-key file, refusing to do so if it's accessible by group/other. Shares
+/// its permission check with `keystore::check_permissions` rather than duplicating it.
+fn read_secret_key_file(path: &Path) -> Result<String> {
+    keystore::check_permissions(path)?;
+    fs::read_to_string(path).map_err(Into::into)
+}
+
 #[tokio::main]
 async fn main() -> Result<()> {
+    let opt = Opt::parse();
+    let addr = opt.addr;
+
     // For client, default to log to std::out
-    let logging_targets = vec![
+    let mut logging_targets = vec![
         ("safenode".to_string(), Level::INFO),
         ("sn_transfers".to_string(), Level::INFO),
         ("sn_networking".to_string(), Level::INFO),
         ("sn_node".to_string(), Level::INFO),
     ];
-    let _log_appender_guard = LogBuilder::new(logging_targets).initialize()?;
+    if let Ok(rust_log) = std::env::var("RUST_LOG") {
+        merge_log_level(&mut logging_targets, &rust_log)?;
+    }
+    for spec in &opt.log_level {
+        merge_log_level(&mut logging_targets, spec)?;
+    }
 
-    let opt = Opt::parse();
-    let addr = opt.addr;
+    let _log_appender_guard = LogBuilder::new(logging_targets)
+        .output_dest(opt.log_output)
+        .initialize()?;
+
+    // `transfers` and `rescan` each manage their own connection to the network alongside
+    // a client wallet; every other subcommand talks to the node over this single shared
+    // RPC connection.
+    if let Cmd::TransfersEvents {
+        key,
+        log_cash_notes,
+        peers,
+    } = opt.cmd
+    {
+        let sk = resolve_secret_key(key)?;
+        let bootstrap_peers = parse_peers_args(peers).await?;
+        let bootstrap_peers = if bootstrap_peers.is_empty() {
+            // empty vec is returned if `local-discovery` flag is provided
+            None
+        } else {
+            Some(bootstrap_peers)
+        };
+
+        return transfers_events(addr, sk, log_cash_notes, bootstrap_peers).await;
+    }
+
+    if let Cmd::Rescan {
+        key,
+        stop_gap,
+        log_cash_notes,
+        peers,
+    } = opt.cmd
+    {
+        let sk = resolve_secret_key(key)?;
+        let bootstrap_peers = parse_peers_args(peers).await?;
+        let bootstrap_peers = if bootstrap_peers.is_empty() {
+            None
+        } else {
+            Some(bootstrap_peers)
+        };
+
+        return wallet_rescan(sk, stop_gap, log_cash_notes, bootstrap_peers).await;
+    }
+
+    let endpoint = format!("https://{addr}");
+    let mut client = SafeNodeClient::connect(endpoint).await?;
 
     match opt.cmd {
-        Cmd::Info => node_info(addr).await,
-        Cmd::Netinfo => network_info(addr).await,
-        Cmd::Events => node_events(addr).await,
-        Cmd::TransfersEvents {
-            sk,
-            log_cash_notes,
-            peers,
-        } => {
-            let bootstrap_peers = parse_peers_args(peers).await?;
-            let bootstrap_peers = if bootstrap_peers.is_empty() {
-                // empty vec is returned if `local-discovery` flag is provided
-                None
-            } else {
-                Some(bootstrap_peers)
-            };
-
-            transfers_events(addr, sk, log_cash_notes, bootstrap_peers).await
+        Cmd::Info => node_info(&mut client, addr).await,
+        Cmd::Netinfo => network_info(&mut client).await,
+        Cmd::Credits => node_credits(&mut client).await,
+        Cmd::Interactive => interactive_session(client, addr).await,
+        Cmd::Events => node_events(&mut client).await,
+        Cmd::TransfersEvents { .. } => unreachable!("handled above"),
+        Cmd::Rescan { .. } => unreachable!("handled above"),
+        Cmd::Subscribe { topic } => gossipsub_subscribe(&mut client, topic).await,
+        Cmd::Unsubscribe { topic } => gossipsub_unsubscribe(&mut client, topic).await,
+        Cmd::Publish { topic, msg } => gossipsub_publish(&mut client, topic, msg).await,
+        Cmd::RewardsAddress { key } => rewards_address(&mut client, key).await,
+        Cmd::Restart { delay_millis } => node_restart(&mut client, delay_millis).await,
+        Cmd::Stop { delay_millis } => node_stop(&mut client, delay_millis).await,
+        Cmd::Update { delay_millis } => node_update(&mut client, delay_millis).await,
+    }
+}
+
+/// Opens one RPC connection, streams node events on it in the background, and runs a
+/// line-oriented prompt so the same connection can be used to issue further subcommands
+/// without reconnecting or blocking on the event stream.
+async fn interactive_session(client: RpcClient, addr: SocketAddr) -> Result<()> {
+    println!("Connected to {addr}.");
+    println!("Node events will print below as they arrive; type a subcommand (`info`, `netinfo`, `subscribe <topic>`, `publish <topic> <msg>`, `rewards <sk>`, `restart`, ...) or `quit` to exit.");
+    println!();
+
+    let mut events_client = client.clone();
+    tokio::spawn(async move {
+        if let Err(err) = node_events(&mut events_client).await {
+            println!("Node events stream ended: {err}");
+        }
+    });
+
+    let mut client = client;
+    let mut lines = BufReader::new(tokio::io::stdin()).lines();
+    loop {
+        print!("> ");
+        std::io::stdout().flush().ok();
+
+        let Some(line) = lines.next_line().await? else {
+            break;
+        };
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        if line == "quit" || line == "exit" {
+            break;
+        }
+
+        let words = std::iter::once("interactive").chain(line.split_whitespace());
+        match Cmd::try_parse_from(words) {
+            Ok(cmd) => {
+                if let Err(err) = run_interactive_cmd(&mut client, addr, cmd).await {
+                    println!("Error: {err}");
+                }
+            }
+            Err(err) => println!("{err}"),
         }
-        Cmd::Subscribe { topic } => gossipsub_subscribe(addr, topic).await,
-        Cmd::Unsubscribe { topic } => gossipsub_unsubscribe(addr, topic).await,
-        Cmd::Publish { topic, msg } => gossipsub_publish(addr, topic, msg).await,
-        Cmd::RewardsAddress { sk } => rewards_address(addr, sk).await,
-        Cmd::Restart { delay_millis } => node_restart(addr, delay_millis).await,
-        Cmd::Stop { delay_millis } => node_stop(addr, delay_millis).await,
-        Cmd::Update { delay_millis } => node_update(addr, delay_millis).await,
     }
+
+    Ok(())
 }
 
-pub async fn node_info(addr: SocketAddr) -> Result<()> {
-    let endpoint = format!("https://{addr}");
-    let mut client = SafeNodeClient::connect(endpoint.clone()).await?;
-    let response = client.node_info(Request::new(NodeInfoRequest {})).await?;
+/// Dispatches a subcommand parsed from the interactive prompt onto the already-open
+/// connection. `events`/`interactive`/`transfers` don't make sense nested inside an
+/// already-running interactive session, so they just print a short explanation.
+async fn run_interactive_cmd(client: &mut RpcClient, addr: SocketAddr, cmd: Cmd) -> Result<()> {
+    match cmd {
+        Cmd::Info => node_info(client, addr).await,
+        Cmd::Netinfo => network_info(client).await,
+        Cmd::Credits => node_credits(client).await,
+        Cmd::Subscribe { topic } => gossipsub_subscribe(client, topic).await,
+        Cmd::Unsubscribe { topic } => gossipsub_unsubscribe(client, topic).await,
+        Cmd::Publish { topic, msg } => gossipsub_publish(client, topic, msg).await,
+        Cmd::RewardsAddress { key } => rewards_address(client, key).await,
+        Cmd::Restart { delay_millis } => node_restart(client, delay_millis).await,
+        Cmd::Stop { delay_millis } => node_stop(client, delay_millis).await,
+        Cmd::Update { delay_millis } => node_update(client, delay_millis).await,
+        Cmd::Events | Cmd::Interactive | Cmd::TransfersEvents { .. } | Cmd::Rescan { .. } => {
+            println!(
+                "Node events are already streaming in the background of this interactive session; \
+                 run this subcommand as a standalone command instead."
+            );
+            Ok(())
+        }
+    }
+}
+
+pub async fn node_info(client: &mut RpcClient, addr: SocketAddr) -> Result<()> {
+    let response = map_rpc_status(client.node_info(Request::new(NodeInfoRequest {})).await)?;
     let node_info = response.get_ref();
     let peer_id = PeerId::from_bytes(&node_info.peer_id)?;
 
     println!("Node info:");
     println!("==========");
-    println!("RPC endpoint: {endpoint}");
+    println!("RPC endpoint: https://{addr}");
     println!("Peer Id: {peer_id}");
     println!("Logs dir: {}", node_info.log_dir);
     println!("PID: {}", node_info.pid);
@@ -188,12 +479,12 @@ pub async fn node_info(addr: SocketAddr) -> Result<()> {
     Ok(())
 }
 
-pub async fn network_info(addr: SocketAddr) -> Result<()> {
-    let endpoint = format!("https://{addr}");
-    let mut client = SafeNodeClient::connect(endpoint).await?;
-    let response = client
-        .network_info(Request::new(NetworkInfoRequest {}))
-        .await?;
+pub async fn network_info(client: &mut RpcClient) -> Result<()> {
+    let response = map_rpc_status(
+        client
+            .network_info(Request::new(NetworkInfoRequest {}))
+            .await,
+    )?;
     let network_info = response.get_ref();
 
     println!("Node's connections to the Network:");
@@ -214,12 +505,28 @@ pub async fn network_info(addr: SocketAddr) -> Result<()> {
     Ok(())
 }
 
-pub async fn node_events(addr: SocketAddr) -> Result<()> {
-    let endpoint = format!("https://{addr}");
-    let mut client = SafeNodeClient::connect(endpoint).await?;
-    let response = client
-        .node_events(Request::new(NodeEventsRequest {}))
-        .await?;
+pub async fn node_credits(client: &mut RpcClient) -> Result<()> {
+    let response = map_rpc_status(client.credits(Request::new(CreditsRequest {})).await)?;
+    let credits = response.get_ref();
+
+    println!("RPC cost table:");
+    for entry in credits.cost_table.iter() {
+        println!("  {}: {} credit/s", entry.rpc_name, entry.cost);
+    }
+    println!();
+    println!("Current balance: {}", credits.current);
+    println!("Max balance: {}", credits.max);
+    println!("Recharge rate: {} credit/s per second", credits.recharge_rate);
+
+    Ok(())
+}
+
+pub async fn node_events(client: &mut RpcClient) -> Result<()> {
+    let response = map_rpc_status(
+        client
+            .node_events(Request::new(NodeEventsRequest {}))
+            .await,
+    )?;
 
     println!("Listening to node events... (press Ctrl+C to exit)");
 
@@ -238,31 +545,29 @@ pub async fn node_events(addr: SocketAddr) -> Result<()> {
 
 pub async fn transfers_events(
     addr: SocketAddr,
-    sk: String,
+    sk: SecretKey,
     log_cash_notes: Option<PathBuf>,
     bootstrap_peers: Option<Vec<Multiaddr>>,
 ) -> Result<()> {
-    let (client, mut wallet, pk) = match SecretKey::from_hex(&sk) {
-        Ok(sk) => {
-            let pk = sk.public_key();
-            let client = Client::new(sk.clone(), bootstrap_peers, None).await?;
-            let main_sk = MainSecretKey::new(sk);
-            let wallet_dir = TempDir::new()?;
-            let wallet = LocalWallet::load_from_main_key(&wallet_dir, main_sk)?;
-            (client, wallet, pk)
-        }
-        Err(err) => return Err(eyre!("Failed to parse hex-encoded SK: {err:?}")),
-    };
+    let pk = sk.public_key();
+    let client = Client::new(sk.clone(), bootstrap_peers, None).await?;
+    let main_sk = MainSecretKey::new(sk);
+    let wallet_dir = TempDir::new()?;
+    let mut wallet = LocalWallet::load_from_main_key(&wallet_dir, main_sk)?;
     let endpoint = format!("https://{addr}");
     let mut node_client = SafeNodeClient::connect(endpoint).await?;
-    let _ = node_client
-        .transfer_notifs_filter(Request::new(TransferNotifsFilterRequest {
-            pk: pk.to_bytes().to_vec(),
-        }))
-        .await?;
-    let response = node_client
-        .node_events(Request::new(NodeEventsRequest {}))
-        .await?;
+    let _ = map_rpc_status(
+        node_client
+            .transfer_notifs_filter(Request::new(TransferNotifsFilterRequest {
+                pk: pk.to_bytes().to_vec(),
+            }))
+            .await,
+    )?;
+    let response = map_rpc_status(
+        node_client
+            .node_events(Request::new(NodeEventsRequest {}))
+            .await,
+    )?;
 
     println!("Listening to transfers notifications for {pk:?}... (press Ctrl+C to exit)");
     if let Some(ref path) = log_cash_notes {
@@ -341,12 +646,127 @@ pub async fn transfers_events(
     Ok(())
 }
 
-pub async fn record_addresses(addr: SocketAddr) -> Result<()> {
-    let endpoint = format!("https://{addr}");
-    let mut client = SafeNodeClient::connect(endpoint).await?;
-    let response = client
-        .record_addresses(Request::new(RecordAddressesRequest {}))
-        .await?;
+/// Number of attempts made to query a single address before the rescan gives up and
+/// aborts. Keeps a transient network error from being miscounted as "address confirmed
+/// empty", which would otherwise trip `stop_gap` early and silently truncate the sweep.
+const MAX_QUERY_ATTEMPTS: usize = 3;
+
+/// Queries a single derived address, retrying on failure. Returns `Ok(None)` only once
+/// the network has confirmed no CashNote lives there, `Ok(Some(_))` if one was found,
+/// and `Err` if every attempt failed — the caller should treat that as a reason to stop
+/// the rescan, not as an empty address.
+async fn query_cash_note_with_retries(
+    client: &Client,
+    address: &SpendAddress,
+) -> Result<Option<CashNote>> {
+    let mut last_err = None;
+    for attempt in 1..=MAX_QUERY_ATTEMPTS {
+        match client.get_cash_note_from_network(address).await {
+            Ok(cash_note) => return Ok(cash_note),
+            Err(err) => {
+                warn!(
+                    "Attempt {attempt}/{MAX_QUERY_ATTEMPTS} to query {address:?} failed: {err:?}"
+                );
+                last_err = Some(err);
+            }
+        }
+    }
+
+    Err(eyre!(
+        "Failed to query address {address:?} after {MAX_QUERY_ATTEMPTS} attempts: {last_err:?}. \
+         Aborting the rescan rather than risk mistaking a network error for an empty address."
+    ))
+}
+
+/// Reconstructs wallet balance by sweeping the network directly for CashNotes, rather
+/// than relying on live `TransferNotif` events. Starting from `main_sk`, addresses are
+/// derived sequentially and queried one by one; the sweep stops once `stop_gap`
+/// consecutive addresses are *confirmed* empty, on the assumption that no CashNotes
+/// exist beyond that point.
+pub async fn wallet_rescan(
+    sk: SecretKey,
+    stop_gap: u64,
+    log_cash_notes: Option<PathBuf>,
+    bootstrap_peers: Option<Vec<Multiaddr>>,
+) -> Result<()> {
+    let client = Client::new(sk.clone(), bootstrap_peers, None).await?;
+    let main_sk = MainSecretKey::new(sk);
+    let wallet_dir = TempDir::new()?;
+    let mut wallet = LocalWallet::load_from_main_key(&wallet_dir, main_sk.clone())?;
+
+    println!("Rescanning the network for CashNotes (stop-gap: {stop_gap})...");
+    if let Some(ref path) = log_cash_notes {
+        // create cash_notes dir
+        fs::create_dir_all(path)?;
+        println!("Writing cash notes to: {}", path.display());
+    }
+    println!();
+
+    let mut index: u64 = 0;
+    let mut consecutive_empty: u64 = 0;
+    let mut found = 0usize;
+
+    while consecutive_empty < stop_gap {
+        let mut derivation_index_bytes = [0u8; 32];
+        derivation_index_bytes[..8].copy_from_slice(&index.to_le_bytes());
+        let derivation_index = DerivationIndex::from_bytes(derivation_index_bytes);
+        let derived_pk = main_sk.main_pubkey().new_unique_pubkey(&derivation_index);
+        let address = SpendAddress::from_unique_pubkey(&derived_pk);
+
+        match query_cash_note_with_retries(&client, &address).await? {
+            Some(cash_note) => {
+                // Mirrors the verification `transfers_events` does for CashNotes unpacked
+                // from a live notification: don't trust whatever the network handed back
+                // just because it was found at the expected address.
+                if let Err(err) = client.verify_cash_note(&cash_note).await {
+                    warn!(
+                        "CashNote returned for derivation index {index} failed verification; \
+                         skipping it: {err:?}"
+                    );
+                    index += 1;
+                    continue;
+                }
+
+                consecutive_empty = 0;
+                found += 1;
+                println!(
+                    "Found CashNote at derivation index {index} with {:?}, value: {}",
+                    cash_note.unique_pubkey(),
+                    cash_note.value()?
+                );
+
+                wallet.deposit(&[cash_note.clone()])?;
+
+                if let Some(ref path) = log_cash_notes {
+                    let unique_pubkey_name = *address.xorname();
+                    let unique_pubkey_file_name =
+                        format!("{}.cash_note", hex::encode(unique_pubkey_name));
+                    let cash_note_file_path = path.join(unique_pubkey_file_name);
+                    println!("Writing cash note to: {}", cash_note_file_path.display());
+                    fs::write(cash_note_file_path, cash_note.to_hex()?)?;
+                }
+            }
+            None => consecutive_empty += 1,
+        }
+
+        index += 1;
+    }
+
+    println!();
+    println!(
+        "Rescan complete: found {found} CashNote/s after {consecutive_empty} consecutive empty addresses."
+    );
+    println!("New wallet balance: {}", wallet.balance());
+
+    Ok(())
+}
+
+pub async fn record_addresses(client: &mut RpcClient) -> Result<()> {
+    let response = map_rpc_status(
+        client
+            .record_addresses(Request::new(RecordAddressesRequest {}))
+            .await,
+    )?;
 
     println!("Records held by the node:");
     for bytes in response.get_ref().addresses.iter() {
@@ -357,54 +777,53 @@ pub async fn record_addresses(addr: SocketAddr) -> Result<()> {
     Ok(())
 }
 
-pub async fn gossipsub_subscribe(addr: SocketAddr, topic: String) -> Result<()> {
-    let endpoint = format!("https://{addr}");
-    let mut client = SafeNodeClient::connect(endpoint).await?;
-    let _response = client
-        .subscribe_to_topic(Request::new(GossipsubSubscribeRequest {
-            topic: topic.clone(),
-        }))
-        .await?;
+pub async fn gossipsub_subscribe(client: &mut RpcClient, topic: String) -> Result<()> {
+    let _response = map_rpc_status(
+        client
+            .subscribe_to_topic(Request::new(GossipsubSubscribeRequest {
+                topic: topic.clone(),
+            }))
+            .await,
+    )?;
     println!("Node successfully received the request to subscribe to topic '{topic}'");
     Ok(())
 }
 
-pub async fn gossipsub_unsubscribe(addr: SocketAddr, topic: String) -> Result<()> {
-    let endpoint = format!("https://{addr}");
-    let mut client = SafeNodeClient::connect(endpoint).await?;
-    let _response = client
-        .unsubscribe_from_topic(Request::new(GossipsubUnsubscribeRequest {
-            topic: topic.clone(),
-        }))
-        .await?;
+pub async fn gossipsub_unsubscribe(client: &mut RpcClient, topic: String) -> Result<()> {
+    let _response = map_rpc_status(
+        client
+            .unsubscribe_from_topic(Request::new(GossipsubUnsubscribeRequest {
+                topic: topic.clone(),
+            }))
+            .await,
+    )?;
     println!("Node successfully received the request to unsubscribe from topic '{topic}'");
     Ok(())
 }
 
-pub async fn gossipsub_publish(addr: SocketAddr, topic: String, msg: String) -> Result<()> {
-    let endpoint = format!("https://{addr}");
-    let mut client = SafeNodeClient::connect(endpoint).await?;
-    let _response = client
-        .publish_on_topic(Request::new(GossipsubPublishRequest {
-            topic: topic.clone(),
-            msg: msg.into(),
-        }))
-        .await?;
+pub async fn gossipsub_publish(client: &mut RpcClient, topic: String, msg: String) -> Result<()> {
+    let _response = map_rpc_status(
+        client
+            .publish_on_topic(Request::new(GossipsubPublishRequest {
+                topic: topic.clone(),
+                msg: msg.into(),
+            }))
+            .await,
+    )?;
     println!("Node successfully received the request to publish on topic '{topic}'");
     Ok(())
 }
 
-pub async fn rewards_address(addr: SocketAddr, sk: String) -> Result<()> {
-    let sk =
-        SecretKey::from_hex(&sk).map_err(|err| eyre!("Failed to parse hex-encoded SK: {err:?}"))?;
+pub async fn rewards_address(client: &mut RpcClient, key: SecretKeyArgs) -> Result<()> {
+    let sk = resolve_secret_key(key)?;
 
-    let endpoint = format!("https://{addr}");
-    let mut client = SafeNodeClient::connect(endpoint).await?;
-    let _response = client
-        .set_rewards_address(Request::new(SetRewardsAddressRequest {
-            sk: sk.to_bytes().to_vec(),
-        }))
-        .await?;
+    let _response = map_rpc_status(
+        client
+            .set_rewards_address(Request::new(SetRewardsAddressRequest {
+                sk: sk.to_bytes().to_vec(),
+            }))
+            .await,
+    )?;
     println!(
         "Node successfully received the request to set the rewards address to {:?}",
         sk.public_key()
@@ -412,12 +831,12 @@ pub async fn rewards_address(addr: SocketAddr, sk: String) -> Result<()> {
     Ok(())
 }
 
-pub async fn node_restart(addr: SocketAddr, delay_millis: u64) -> Result<()> {
-    let endpoint = format!("https://{addr}");
-    let mut client = SafeNodeClient::connect(endpoint).await?;
-    let _response = client
-        .restart(Request::new(RestartRequest { delay_millis }))
-        .await?;
+pub async fn node_restart(client: &mut RpcClient, delay_millis: u64) -> Result<()> {
+    let _response = map_rpc_status(
+        client
+            .restart(Request::new(RestartRequest { delay_millis }))
+            .await,
+    )?;
     println!(
         "Node successfully received the request to restart in {:?}",
         Duration::from_millis(delay_millis)
@@ -425,12 +844,12 @@ pub async fn node_restart(addr: SocketAddr, delay_millis: u64) -> Result<()> {
     Ok(())
 }
 
-pub async fn node_stop(addr: SocketAddr, delay_millis: u64) -> Result<()> {
-    let endpoint = format!("https://{addr}");
-    let mut client = SafeNodeClient::connect(endpoint).await?;
-    let _response = client
-        .stop(Request::new(StopRequest { delay_millis }))
-        .await?;
+pub async fn node_stop(client: &mut RpcClient, delay_millis: u64) -> Result<()> {
+    let _response = map_rpc_status(
+        client
+            .stop(Request::new(StopRequest { delay_millis }))
+            .await,
+    )?;
     println!(
         "Node successfully received the request to stop in {:?}",
         Duration::from_millis(delay_millis)
@@ -438,12 +857,12 @@ pub async fn node_stop(addr: SocketAddr, delay_millis: u64) -> Result<()> {
     Ok(())
 }
 
-pub async fn node_update(addr: SocketAddr, delay_millis: u64) -> Result<()> {
-    let endpoint = format!("https://{addr}");
-    let mut client = SafeNodeClient::connect(endpoint).await?;
-    let _response = client
-        .update(Request::new(UpdateRequest { delay_millis }))
-        .await?;
+pub async fn node_update(client: &mut RpcClient, delay_millis: u64) -> Result<()> {
+    let _response = map_rpc_status(
+        client
+            .update(Request::new(UpdateRequest { delay_millis }))
+            .await,
+    )?;
     println!(
         "Node successfully received the request to try to update in {:?}",
         Duration::from_millis(delay_millis)
@@ -0,0 +1,120 @@
+// Copyright 2023 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+//! A minimal on-disk keystore for the RPC client, so an operator's reward/transfer keys
+//! can live in one file and be referenced by a short alias (e.g. `--key-alias payout`)
+//! instead of ever being typed out as raw hex.
+
+use bls::SecretKey;
+use eyre::{eyre, Result};
+use std::{collections::BTreeMap, fs, path::PathBuf};
+
+#[cfg(unix)]
+use std::os::unix::fs::PermissionsExt;
+
+/// Default location of the keystore file: `~/.safe/node/keystore`.
+///
+/// The file is a plain list of `alias = hex-encoded-secret-key` lines, one per line,
+/// with `#`-prefixed comments and blank lines ignored.
+pub fn default_path() -> Result<PathBuf> {
+    Ok(home_dir()?.join(".safe").join("node").join("keystore"))
+}
+
+/// Looks up the current user's home directory via the environment, avoiding a pull
+/// on an extra crate dependency just for this.
+fn home_dir() -> Result<PathBuf> {
+    if let Ok(home) = std::env::var("HOME") {
+        if !home.is_empty() {
+            return Ok(PathBuf::from(home));
+        }
+    }
+
+    #[cfg(windows)]
+    if let Ok(profile) = std::env::var("USERPROFILE") {
+        if !profile.is_empty() {
+            return Ok(PathBuf::from(profile));
+        }
+    }
+
+    Err(eyre!("Could not determine home directory"))
+}
+
+/// An on-disk collection of named BLS secret keys.
+pub struct Keystore {
+    keys: BTreeMap<String, String>,
+}
+
+impl Keystore {
+    /// Loads the keystore from the default location. A missing file is treated as empty
+    /// so that looking up any alias fails with a clear "not found" error.
+    pub fn load_default() -> Result<Self> {
+        Self::load(&default_path()?)
+    }
+
+    /// Loads the keystore from `path`, rejecting it if its permissions allow group/other
+    /// access.
+    pub fn load(path: &std::path::Path) -> Result<Self> {
+        if !path.exists() {
+            return Ok(Self {
+                keys: BTreeMap::new(),
+            });
+        }
+
+        check_permissions(path)?;
+        let contents = fs::read_to_string(path)
+            .map_err(|err| eyre!("Failed to read keystore at {}: {err}", path.display()))?;
+
+        let mut keys = BTreeMap::new();
+        for line in contents.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (alias, hex) = line.split_once('=').ok_or_else(|| {
+                eyre!(
+                    "Invalid line in keystore at {}: expected `alias = hex-key`, got '{line}'",
+                    path.display()
+                )
+            })?;
+            keys.insert(alias.trim().to_string(), hex.trim().to_string());
+        }
+
+        Ok(Self { keys })
+    }
+
+    /// Looks up and decodes the secret key stored under `alias`.
+    pub fn get(&self, alias: &str) -> Result<SecretKey> {
+        let hex = self
+            .keys
+            .get(alias)
+            .ok_or_else(|| eyre!("No key found in keystore for alias '{alias}'"))?;
+        SecretKey::from_hex(hex)
+            .map_err(|err| eyre!("Invalid key stored in keystore for alias '{alias}': {err:?}"))
+    }
+}
+
+/// Rejects a keystore/secret-key file that has any permission bit (read, write or
+/// execute) set for group/other.
+#[cfg(unix)]
+pub fn check_permissions(path: &std::path::Path) -> Result<()> {
+    let mode = fs::metadata(path)?.permissions().mode();
+    if mode & 0o077 != 0 {
+        return Err(eyre!(
+            "Refusing to read '{}': file permissions are too open ({:o}). Run `chmod 600 {}`.",
+            path.display(),
+            mode & 0o777,
+            path.display()
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(not(unix))]
+pub fn check_permissions(_path: &std::path::Path) -> Result<()> {
+    Ok(())
+}
@@ -0,0 +1,106 @@
+// Copyright 2023 MaidSafe.net limited.
+//
+// This SAFE Network Software is licensed to you under The General Public License (GPL), version 3.
+// Unless required by applicable law or agreed to in writing, the SAFE Network Software distributed
+// under the GPL Licence is distributed on an "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied. Please review the Licences for the specific language governing
+// permissions and limitations relating to use of the SAFE Network Software.
+
+//! Per-connection credit accounting for the safenode RPC service, backing the `Credits`
+//! RPC defined in `proto/credits.proto`.
+//!
+//! Not yet wired into `rpc_service.rs`: that file, and the rest of the `sn_node` library
+//! crate it belongs to, aren't part of this change. The remaining integration step is for
+//! `rpc_service.rs` to keep one [`CreditLedger`] per connected client and call
+//! [`CreditLedger::try_consume`] before running each handler's body, returning the
+//! resulting `Err(Status)` straight to the caller on rejection.
+
+use std::time::Instant;
+use tonic::{Code, Status};
+
+/// gRPC status code returned when a caller's balance can't cover an RPC's cost.
+pub const INSUFFICIENT_CREDITS_CODE: Code = Code::ResourceExhausted;
+
+/// Cost charged against a caller's balance for each RPC, keyed by the method name also
+/// reported in `CreditTableEntry::rpc_name`. Cheap introspection calls are effectively
+/// free; the calls that mutate node state or push data into the network are the ones
+/// worth throttling.
+pub const COST_TABLE: &[(&str, f64)] = &[
+    ("info", 0.0),
+    ("netinfo", 0.0),
+    ("credits", 0.0),
+    ("record_addresses", 0.0),
+    ("events", 0.0),
+    ("subscribe", 1.0),
+    ("unsubscribe", 1.0),
+    ("publish", 5.0),
+    ("rewards_address", 1.0),
+    ("transfer_notifs_filter", 5.0),
+    ("restart", 20.0),
+    ("stop", 20.0),
+    ("update", 20.0),
+];
+
+fn cost_of(rpc_name: &str) -> f64 {
+    COST_TABLE
+        .iter()
+        .find(|(name, _)| *name == rpc_name)
+        .map(|(_, cost)| *cost)
+        .unwrap_or(0.0)
+}
+
+/// A single connected client's credit balance, recharging linearly with elapsed
+/// wall-clock time up to `max`.
+pub struct CreditLedger {
+    current: f64,
+    max: f64,
+    recharge_rate: f64,
+    last_recharge: Instant,
+}
+
+impl CreditLedger {
+    /// Creates a ledger starting at a full `max`-credit balance.
+    pub fn new(max: f64, recharge_rate: f64) -> Self {
+        Self {
+            current: max,
+            max,
+            recharge_rate,
+            last_recharge: Instant::now(),
+        }
+    }
+
+    fn recharge(&mut self) {
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.last_recharge).as_secs_f64();
+        self.current = (self.current + self.recharge_rate * elapsed).min(self.max);
+        self.last_recharge = now;
+    }
+
+    /// Recharges, then deducts `rpc_name`'s cost from the balance if it can be afforded.
+    /// Returns a status with [`INSUFFICIENT_CREDITS_CODE`] without deducting anything if
+    /// it can't; the caller
+    /// should map that straight into the RPC's response rather than running the handler.
+    pub fn try_consume(&mut self, rpc_name: &str) -> Result<(), Status> {
+        self.recharge();
+        let cost = cost_of(rpc_name);
+        if self.current < cost {
+            return Err(Status::new(
+                INSUFFICIENT_CREDITS_CODE,
+                format!(
+                    "Insufficient credits to call '{rpc_name}' (costs {cost}, current balance {:.2})",
+                    self.current
+                ),
+            ));
+        }
+        self.current -= cost;
+        Ok(())
+    }
+
+    /// Recharges, then returns `(current, max, recharge_rate)` for the `Credits` RPC
+    /// response, so a reported balance always reflects elapsed time rather than
+    /// whatever was left over after the last deduction.
+    pub fn snapshot(&mut self) -> (f64, f64, f64) {
+        self.recharge();
+        (self.current, self.max, self.recharge_rate)
+    }
+}